@@ -1,6 +1,10 @@
 //! Julian day is the continuous count of days since the beginning of the Julian Period.
 //! This crate implements a method to convert a JulianDay to and from the chrono's NaiveDate.
 //!
+//! `JulianDayFloat` extends this with sub-day precision, following the astronomical
+//! convention that a Julian Date is a real number whose day boundary falls at noon UTC
+//! rather than midnight.
+//!
 //! # Example
 //!
 //! ```
@@ -30,10 +34,13 @@
 //!  }
 //!  ```
 
-use chrono::{self, Datelike, NaiveDate};
+use chrono::{self, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use std::convert::From;
+use std::ops::{Add, Sub};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct JulianDay(i32);
 
 impl From<NaiveDate> for JulianDay {
@@ -83,9 +90,201 @@ impl JulianDay {
 
         NaiveDate::from_ymd(year as i32, month as u32, day as u32)
     }
+
+    /// Get a JulianDay from a Unix timestamp (seconds since 1970-01-01T00:00:00 UTC)
+    pub fn from_unix_timestamp(timestamp: i64) -> Self {
+        JulianDay(2440588 + timestamp.div_euclid(86400) as i32)
+    }
+
+    /// Convert a JulianDay to a Unix timestamp (seconds since 1970-01-01T00:00:00 UTC)
+    pub fn to_unix_timestamp(self) -> i64 {
+        (self.inner() as i64 - 2440588) * 86400
+    }
+
+    /// Get the day of the week, computed directly from the day count rather than by
+    /// round-tripping through a NaiveDate. JDN 0 fell on a Monday.
+    pub fn weekday(self) -> chrono::Weekday {
+        match self.inner().rem_euclid(7) {
+            0 => chrono::Weekday::Mon,
+            1 => chrono::Weekday::Tue,
+            2 => chrono::Weekday::Wed,
+            3 => chrono::Weekday::Thu,
+            4 => chrono::Weekday::Fri,
+            5 => chrono::Weekday::Sat,
+            6 => chrono::Weekday::Sun,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Parse a loosely-formed ISO-8601 date or date-time string into a JulianDay.
+    ///
+    /// Accepts a bare `YYYY-MM-DD`, a full `YYYY-MM-DDTHH:MM:SS`, a space instead of `T`,
+    /// a missing seconds field, and a trailing `Z`. Any absent time component defaults to
+    /// midnight.
+    pub fn from_fuzzy_str(s: &str) -> Result<Self, ParseError> {
+        let s = s.trim();
+        let s = s.strip_suffix('Z').unwrap_or(s);
+        let s = s.replacen(' ', "T", 1);
+
+        let mut parts = s.splitn(2, 'T');
+        let date_part = parts.next().ok_or(ParseError::InvalidFormat)?;
+        let time_part = parts.next();
+
+        let mut date_fields = date_part.splitn(3, '-');
+        let year = parse_field(date_fields.next())?;
+        let month = parse_field(date_fields.next())?;
+        let day = parse_field(date_fields.next())?;
+        if date_fields.next().is_some() {
+            return Err(ParseError::InvalidFormat);
+        }
+
+        let (hour, minute, second) = match time_part {
+            None => (0, 0, 0),
+            Some(time_part) => {
+                let mut time_fields = time_part.splitn(3, ':');
+                let hour = parse_field(time_fields.next())?;
+                let minute = parse_field(time_fields.next())?;
+                let second = match time_fields.next() {
+                    Some(field) => parse_field(Some(field))?,
+                    None => 0,
+                };
+                if time_fields.next().is_some() {
+                    return Err(ParseError::InvalidFormat);
+                }
+                (hour, minute, second)
+            }
+        };
+
+        let date = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+            .ok_or(ParseError::OutOfRange)?;
+        NaiveTime::from_hms_opt(hour as u32, minute as u32, second as u32)
+            .ok_or(ParseError::OutOfRange)?;
+
+        Ok(JulianDay::from(date))
+    }
+}
+
+/// Parse a single numeric date/time field, failing with `ParseError::InvalidFormat` if it is
+/// missing or not a number.
+fn parse_field(field: Option<&str>) -> Result<i32, ParseError> {
+    field
+        .ok_or(ParseError::InvalidFormat)?
+        .parse()
+        .map_err(|_| ParseError::InvalidFormat)
+}
+
+/// An error encountered while parsing a fuzzy date/time string with `JulianDay::from_fuzzy_str`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    /// The string did not match any of the accepted date/time formats.
+    InvalidFormat,
+    /// The string matched a format, but one of its fields was out of range (e.g. month 13).
+    OutOfRange,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidFormat => write!(f, "could not parse date/time string"),
+            ParseError::OutOfRange => write!(f, "date/time field out of range"),
+        }
+    }
 }
 
+impl std::error::Error for ParseError {}
+
+impl Add<i32> for JulianDay {
+    type Output = JulianDay;
+
+    /// Shift a JulianDay forward by a number of days
+    fn add(self, days: i32) -> Self::Output {
+        JulianDay(self.inner() + days)
+    }
+}
+
+impl Sub<i32> for JulianDay {
+    type Output = JulianDay;
+
+    /// Shift a JulianDay backward by a number of days
+    fn sub(self, days: i32) -> Self::Output {
+        JulianDay(self.inner() - days)
+    }
+}
+
+impl Sub<JulianDay> for JulianDay {
+    type Output = i32;
+
+    /// Get the difference, in days, between two JulianDays
+    fn sub(self, other: JulianDay) -> i32 {
+        self.inner() - other.inner()
+    }
+}
+
+/// A Julian Date with sub-day precision, stored as a real number whose day boundary
+/// falls at noon UTC rather than midnight.
 #[derive(Clone, Debug, PartialEq)]
+pub struct JulianDayFloat(f64);
+
+impl From<NaiveDateTime> for JulianDayFloat {
+    /// Get a JulianDayFloat from a NaiveDateTime
+    fn from(datetime: NaiveDateTime) -> Self {
+        let jdn = JulianDay::from(datetime.date()).inner() as f64;
+        let seconds_since_midnight = datetime.hour() as f64 * 3600.0
+            + datetime.minute() as f64 * 60.0
+            + datetime.second() as f64;
+
+        JulianDayFloat(jdn - 0.5 + seconds_since_midnight / 86400.0)
+    }
+}
+
+impl JulianDayFloat {
+    /// Construct a new JulianDayFloat
+    pub fn new(day: f64) -> Self {
+        JulianDayFloat(day)
+    }
+
+    /// Get the value of JulianDayFloat as f64
+    pub fn inner(self) -> f64 {
+        let JulianDayFloat(day) = self;
+        day
+    }
+
+    /// Convert a JulianDayFloat to a NaiveDateTime
+    pub fn to_datetime(self) -> NaiveDateTime {
+        let noon_based = self.inner() + 0.5;
+        let mut jdn = noon_based.floor();
+        let fraction = noon_based - jdn;
+
+        let mut seconds = (fraction * 86400.0).round() as u32;
+        if seconds >= 86400 {
+            // Rounding a fraction just below 1.0 can carry into the next day.
+            seconds -= 86400;
+            jdn += 1.0;
+        }
+
+        let date = JulianDay::new(jdn as i32).to_date();
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0)
+            .expect("seconds is in 0..86400 after the carry above");
+
+        date.and_time(time)
+    }
+
+    /// Get a JulianDayFloat from a Unix timestamp, in fractional seconds since
+    /// 1970-01-01T00:00:00 UTC
+    pub fn from_unix_timestamp(timestamp: f64) -> Self {
+        JulianDayFloat(2440587.5 + timestamp / 86400.0)
+    }
+
+    /// Convert a JulianDayFloat to a Unix timestamp, in fractional seconds since
+    /// 1970-01-01T00:00:00 UTC
+    pub fn to_unix_timestamp(self) -> f64 {
+        (self.inner() - 2440587.5) * 86400.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ModifiedJulianDay (i32);
 
 impl From<NaiveDate> for ModifiedJulianDay {
@@ -107,15 +306,99 @@ impl ModifiedJulianDay {
     }
 
     pub fn to_date (self) -> NaiveDate {
-        let jd = JulianDay(self.inner() + 2400001); 
+        let jd = JulianDay(self.inner() + 2400001);
         jd.to_date()
     }
+
+    /// Get the day of the week, computed directly from the day count rather than by
+    /// round-tripping through a NaiveDate.
+    pub fn weekday(self) -> chrono::Weekday {
+        let jd = JulianDay(self.inner() + 2400001);
+        jd.weekday()
+    }
+}
+
+impl Add<i32> for ModifiedJulianDay {
+    type Output = ModifiedJulianDay;
+
+    /// Shift a ModifiedJulianDay forward by a number of days
+    fn add(self, days: i32) -> Self::Output {
+        ModifiedJulianDay(self.inner() + days)
+    }
+}
+
+impl Sub<i32> for ModifiedJulianDay {
+    type Output = ModifiedJulianDay;
+
+    /// Shift a ModifiedJulianDay backward by a number of days
+    fn sub(self, days: i32) -> Self::Output {
+        ModifiedJulianDay(self.inner() - days)
+    }
+}
+
+impl Sub<ModifiedJulianDay> for ModifiedJulianDay {
+    type Output = i32;
+
+    /// Get the difference, in days, between two ModifiedJulianDays
+    fn sub(self, other: ModifiedJulianDay) -> i32 {
+        self.inner() - other.inner()
+    }
+}
+
+/// A date in the proleptic Julian calendar, as distinct from the Gregorian calendar that
+/// `JulianDay::to_date`/`From<NaiveDate>` use. Needed for historical dates before the 1582
+/// Gregorian reform.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JulianCalendarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl From<JulianDay> for JulianCalendarDate {
+    /// Get a JulianCalendarDate from a JulianDay
+    fn from(jd: JulianDay) -> Self {
+        let jd = jd.inner();
+
+        let c = jd + 32082;
+        let d = (4 * c + 3) / 1461;
+        let e = c - 1461 * d / 4;
+        let m = (5 * e + 2) / 153;
+
+        let day = e - (153 * m + 2) / 5 + 1;
+        let month = m + 3 - 12 * (m / 10);
+        let year = d - 4800 + m / 10;
+
+        JulianCalendarDate {
+            year,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+}
+
+impl JulianCalendarDate {
+    /// Convert a JulianCalendarDate to a JulianDay
+    pub fn to_julian_day(self) -> JulianDay {
+        let day = self.day as i32;
+        let month = self.month as i32;
+        let year = self.year;
+
+        let a = (14 - month) / 12;
+        let y = year + 4800 - a;
+        let m = month + 12 * a - 3;
+
+        let jd = day + (153 * m + 2) / 5 + y * 365 + y / 4 - 32083;
+
+        JulianDay::new(jd)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{JulianDay, ModifiedJulianDay};
+    use crate::{JulianCalendarDate, JulianDay, JulianDayFloat, ModifiedJulianDay, ParseError};
     use chrono::NaiveDate;
+    use std::collections::HashSet;
 
     #[test]
     fn julian_to_naivedate() {
@@ -161,4 +444,258 @@ mod tests {
         let mjd = ModifiedJulianDay::from(date);
         assert_eq!(mjd, ModifiedJulianDay(-1));
     }
+
+    #[test]
+    fn naivedatetime_to_julianfloat() {
+        let noon = NaiveDate::from_ymd(2020, 2, 18).and_hms(12, 0, 0);
+        assert_eq!(JulianDayFloat::from(noon), JulianDayFloat::new(2458898.0));
+
+        let midnight = NaiveDate::from_ymd(2020, 2, 18).and_hms(0, 0, 0);
+        assert_eq!(JulianDayFloat::from(midnight), JulianDayFloat::new(2458897.5));
+    }
+
+    #[test]
+    fn julianfloat_to_naivedatetime_round_trip() {
+        let datetime = NaiveDate::from_ymd(2020, 2, 18).and_hms(18, 30, 15);
+        let jd = JulianDayFloat::from(datetime);
+
+        assert_eq!(jd.to_datetime(), datetime);
+    }
+
+    #[test]
+    fn julianfloat_to_naivedatetime_carries_rounding_overflow() {
+        // A fraction just below 1.0 rounds up to a full day's worth of seconds; this must
+        // carry into the next day instead of panicking.
+        let jd = JulianDayFloat::new(2440588.4999951);
+        let expected = JulianDay::new(2440589).to_date().and_hms(0, 0, 0);
+
+        assert_eq!(jd.to_datetime(), expected);
+    }
+
+    #[test]
+    fn julianday_to_juliancalendardate() {
+        // JD 0, the epoch of the Julian Period, fell on 1 Jan 4713 BC in the Julian calendar.
+        let cal = JulianCalendarDate::from(JulianDay::new(0));
+        assert_eq!(
+            cal,
+            JulianCalendarDate {
+                year: -4712,
+                month: 1,
+                day: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn juliancalendardate_to_julianday_round_trip() {
+        let cal = JulianCalendarDate {
+            year: -4712,
+            month: 1,
+            day: 1,
+        };
+        assert_eq!(cal.to_julian_day(), JulianDay::new(0));
+    }
+
+    #[test]
+    fn gregorian_reform_offset() {
+        // 15 Oct 1582 in the Gregorian calendar (the day the reform took effect) is the same
+        // Julian Day as 5 Oct 1582 in the proleptic Julian calendar: the reform skipped ten
+        // civil days, it did not skip any days of the underlying count.
+        let jd = JulianDay::new(2299161);
+
+        assert_eq!(jd.clone().to_date(), NaiveDate::from_ymd(1582, 10, 15));
+        assert_eq!(
+            JulianCalendarDate::from(jd),
+            JulianCalendarDate {
+                year: 1582,
+                month: 10,
+                day: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn fuzzy_str_bare_date() {
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18"),
+            Ok(JulianDay::new(2458898))
+        );
+    }
+
+    #[test]
+    fn fuzzy_str_t_separator() {
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18T18:30:15"),
+            Ok(JulianDay::new(2458898))
+        );
+    }
+
+    #[test]
+    fn fuzzy_str_space_separator() {
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18 18:30:15"),
+            Ok(JulianDay::new(2458898))
+        );
+    }
+
+    #[test]
+    fn fuzzy_str_missing_seconds() {
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18T18:30"),
+            Ok(JulianDay::new(2458898))
+        );
+    }
+
+    #[test]
+    fn fuzzy_str_trailing_z() {
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18T18:30:15Z"),
+            Ok(JulianDay::new(2458898))
+        );
+    }
+
+    #[test]
+    fn fuzzy_str_invalid_format() {
+        assert_eq!(
+            JulianDay::from_fuzzy_str("not-a-date"),
+            Err(ParseError::InvalidFormat)
+        );
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18-01"),
+            Err(ParseError::InvalidFormat)
+        );
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18T18:30:15:00"),
+            Err(ParseError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn fuzzy_str_out_of_range() {
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-13-18"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            JulianDay::from_fuzzy_str("2020-02-18T24:00:00"),
+            Err(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn julianday_add_sub_days() {
+        let jd = JulianDay::new(2458898);
+        assert_eq!(jd.clone() + 10, JulianDay::new(2458908));
+        assert_eq!(jd - 10, JulianDay::new(2458888));
+    }
+
+    #[test]
+    fn julianday_sub_gives_day_interval() {
+        let jd1 = JulianDay::new(2458898);
+        let jd2 = JulianDay::new(2458908);
+        assert_eq!(jd2 - jd1, 10);
+    }
+
+    #[test]
+    fn julianday_ord_and_hash() {
+        let earlier = JulianDay::new(2458898);
+        let later = JulianDay::new(2458908);
+        assert!(earlier < later);
+
+        let mut days = vec![later.clone(), earlier.clone()];
+        days.sort();
+        assert_eq!(days, vec![earlier.clone(), later.clone()]);
+
+        let mut set = HashSet::new();
+        set.insert(earlier.clone());
+        assert!(set.contains(&earlier));
+        assert!(!set.contains(&later));
+    }
+
+    #[test]
+    fn mjd_add_sub_days() {
+        let mjd = ModifiedJulianDay::new(58897);
+        assert_eq!(mjd.clone() + 10, ModifiedJulianDay::new(58907));
+        assert_eq!(mjd - 10, ModifiedJulianDay::new(58887));
+    }
+
+    #[test]
+    fn mjd_sub_gives_day_interval() {
+        let mjd1 = ModifiedJulianDay::new(58897);
+        let mjd2 = ModifiedJulianDay::new(58907);
+        assert_eq!(mjd2 - mjd1, 10);
+    }
+
+    #[test]
+    fn mjd_ord_and_hash() {
+        let earlier = ModifiedJulianDay::new(58897);
+        let later = ModifiedJulianDay::new(58907);
+        assert!(earlier < later);
+
+        let mut days = vec![later.clone(), earlier.clone()];
+        days.sort();
+        assert_eq!(days, vec![earlier.clone(), later.clone()]);
+
+        let mut set = HashSet::new();
+        set.insert(earlier.clone());
+        assert!(set.contains(&earlier));
+        assert!(!set.contains(&later));
+    }
+
+    #[test]
+    fn julianday_unix_timestamp_anchor() {
+        assert_eq!(JulianDay::from_unix_timestamp(0), JulianDay::new(2440588));
+        assert_eq!(JulianDay::new(2440588).to_unix_timestamp(), 0);
+    }
+
+    #[test]
+    fn julianday_unix_timestamp_negative() {
+        // A naive `/` truncates towards zero, which would put one second before the epoch
+        // on the same JDN as the epoch itself; div_euclid must floor towards the previous day.
+        assert_eq!(JulianDay::from_unix_timestamp(-1), JulianDay::new(2440587));
+        assert_eq!(JulianDay::new(2440587).to_unix_timestamp(), -86400);
+    }
+
+    #[test]
+    fn julianfloat_unix_timestamp_round_trip() {
+        assert_eq!(
+            JulianDayFloat::from_unix_timestamp(0.0),
+            JulianDayFloat::new(2440587.5)
+        );
+        assert_eq!(JulianDayFloat::new(2440587.5).to_unix_timestamp(), 0.0);
+
+        let timestamp = -86400.0 * 1.5;
+        let jd = JulianDayFloat::from_unix_timestamp(timestamp);
+        assert_eq!(jd.to_unix_timestamp(), timestamp);
+    }
+
+    #[test]
+    fn julianday_weekday() {
+        assert_eq!(JulianDay::new(2458898).weekday(), chrono::Weekday::Tue);
+        // JDN 0 fell on a Monday; -5 rem_euclid 7 == 2, i.e. two days after Monday.
+        assert_eq!(JulianDay::new(-5).weekday(), chrono::Weekday::Wed);
+    }
+
+    #[test]
+    fn mjd_weekday() {
+        assert_eq!(ModifiedJulianDay::new(58897).weekday(), chrono::Weekday::Tue);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn julianday_serde_round_trip() {
+        let jd = JulianDay::new(2458898);
+        let json = serde_json::to_string(&jd).unwrap();
+        assert_eq!(json, "2458898");
+        assert_eq!(serde_json::from_str::<JulianDay>(&json).unwrap(), jd);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn mjd_serde_round_trip() {
+        let mjd = ModifiedJulianDay::new(58897);
+        let json = serde_json::to_string(&mjd).unwrap();
+        assert_eq!(json, "58897");
+        assert_eq!(serde_json::from_str::<ModifiedJulianDay>(&json).unwrap(), mjd);
+    }
 }